@@ -0,0 +1,21 @@
+use std::fmt;
+
+/// A target triple, e.g. `x86_64-unknown-linux-gnu`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TargetTriple(String);
+
+impl TargetTriple {
+    pub fn new(s: &str) -> Self {
+        TargetTriple(s.to_owned())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for TargetTriple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
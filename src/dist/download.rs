@@ -0,0 +1,284 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::dist::errors::*;
+use crate::dist::notifications::Notification;
+use crate::utils;
+
+/// How the remote server responded to a ranged request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadResponse {
+    /// The server honored `Range: bytes=N-` and is only sending the
+    /// remainder of the file; bytes already on disk are still valid.
+    PartialContent,
+    /// The server does not support (or ignored) range requests and is
+    /// sending the file from byte 0.
+    FullContent,
+}
+
+/// Abstracts over the `curl`/`reqwest_be` backends, which already accept a
+/// `resume_from` offset and report back whether the server actually
+/// resumed (HTTP 206) or restarted (HTTP 200).
+pub trait DownloadBackend {
+    fn download_to(
+        &self,
+        url: &str,
+        resume_from: u64,
+        dest: &mut dyn Write,
+    ) -> Result<DownloadResponse>;
+}
+
+/// Wraps a `Write` destination to report cumulative bytes written, so a
+/// single component download can drive a byte-progress callback.
+struct ProgressWriter<'a> {
+    inner: &'a mut dyn Write,
+    written: u64,
+    on_progress: &'a dyn Fn(u64),
+}
+
+impl<'a> ProgressWriter<'a> {
+    fn new(inner: &'a mut dyn Write, on_progress: &'a dyn Fn(u64)) -> Self {
+        ProgressWriter {
+            inner,
+            written: 0,
+            on_progress,
+        }
+    }
+}
+
+impl<'a> Write for ProgressWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        (self.on_progress)(self.written);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub struct DownloadCfg<'a> {
+    pub download_dir: &'a Path,
+    // `Sync` so a single `DownloadCfg` can be shared across the worker
+    // threads used for concurrent component downloads.
+    pub notify_handler: &'a (dyn Fn(Notification<'_>) + Sync),
+}
+
+impl<'a> DownloadCfg<'a> {
+    fn cached_path(&self, hash: &str) -> PathBuf {
+        self.download_dir.join(hash)
+    }
+
+    /// Downloads `url` into the persistent download cache (keyed by the
+    /// eventual content hash `hash`), resuming a partial download left
+    /// over from an earlier interrupted attempt when possible. Does
+    /// *not* check the downloaded bytes against `hash` - callers that
+    /// need to verify something (a signature, say) before trusting the
+    /// checksum should call `verify_checksum` themselves afterwards.
+    /// `on_progress`, if given, is called with the cumulative byte count
+    /// as the body streams in.
+    pub fn fetch(
+        &self,
+        url: &str,
+        hash: &str,
+        backend: &dyn DownloadBackend,
+        on_progress: Option<&dyn Fn(u64)>,
+    ) -> Result<PathBuf> {
+        fs::create_dir_all(self.download_dir)?;
+        let path = self.cached_path(hash);
+
+        let existing_len = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if existing_len > 0 {
+            (self.notify_handler)(Notification::ResumingPartialDownload(url, existing_len));
+        }
+
+        let mut file: File = if existing_len > 0 {
+            OpenOptions::new().append(true).open(&path)?
+        } else {
+            File::create(&path)?
+        };
+
+        let response = match on_progress {
+            Some(cb) => {
+                let mut writer = ProgressWriter::new(&mut file, cb);
+                backend.download_to(url, existing_len, &mut writer)?
+            }
+            None => backend.download_to(url, existing_len, &mut file)?,
+        };
+        drop(file);
+
+        if existing_len > 0 && response == DownloadResponse::FullContent {
+            // The server ignored our Range request and sent the file from
+            // scratch: the fresh body was appended after the stale partial
+            // bytes, so the file on disk is garbage. Start over rather than
+            // trying to extend a checksum over a file that no longer
+            // matches what we expect.
+            backend.download_to(url, 0, &mut File::create(&path)?)?;
+        }
+
+        Ok(path)
+    }
+
+    /// Checks `path`'s SHA-256 digest against `hash`, firing
+    /// `ChecksumValid`/`CachedFileChecksumFailed` and removing the file on
+    /// mismatch. Split out from `fetch` so callers that must verify
+    /// something else (e.g. a signature) before trusting the checksum can
+    /// do so in between.
+    pub fn verify_checksum(&self, path: &Path, url: &str, hash: &str) -> Result<()> {
+        let calculated = utils::sha256_file(path)?;
+        if calculated == hash {
+            (self.notify_handler)(Notification::ChecksumValid(url));
+            Ok(())
+        } else {
+            (self.notify_handler)(Notification::CachedFileChecksumFailed);
+            let _ = fs::remove_file(path);
+            Err(Error::ChecksumFailed {
+                url: url.to_owned(),
+                expected: hash.to_owned(),
+                calculated,
+            })
+        }
+    }
+
+    /// `fetch` followed immediately by `verify_checksum` - the common case
+    /// for anything that doesn't need a signature (or other out-of-band
+    /// check) verified before the checksum is trusted.
+    pub fn download(
+        &self,
+        url: &str,
+        hash: &str,
+        backend: &dyn DownloadBackend,
+    ) -> Result<PathBuf> {
+        let path = self.fetch(url, hash, backend, None)?;
+        self.verify_checksum(&path, url, hash)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::test_support::temp_dir;
+    use std::cell::Cell;
+
+    struct MockBackend {
+        response: DownloadResponse,
+        body: &'static [u8],
+        seen_resume_from: Cell<Option<u64>>,
+    }
+
+    impl DownloadBackend for MockBackend {
+        fn download_to(
+            &self,
+            _url: &str,
+            resume_from: u64,
+            dest: &mut dyn Write,
+        ) -> Result<DownloadResponse> {
+            self.seen_resume_from.set(Some(resume_from));
+            dest.write_all(self.body)?;
+            Ok(self.response)
+        }
+    }
+
+    #[test]
+    fn resumes_when_server_returns_206() {
+        let dir = temp_dir("download-resume-206");
+        let hash = utils::sha256_file_from_bytes(b"hello world");
+        fs::write(dir.join(&hash), b"hello ").unwrap();
+
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let backend = MockBackend {
+            response: DownloadResponse::PartialContent,
+            body: b"world",
+            seen_resume_from: Cell::new(None),
+        };
+
+        let path = cfg.download("http://example.com/f", &hash, &backend).unwrap();
+        assert_eq!(backend.seen_resume_from.get(), Some(6));
+        assert_eq!(fs::read(&path).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn restarts_when_server_returns_200() {
+        let dir = temp_dir("download-restart-200");
+        let hash = utils::sha256_file_from_bytes(b"fresh content");
+        fs::write(dir.join(&hash), b"stale partial").unwrap();
+
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let backend = MockBackend {
+            response: DownloadResponse::FullContent,
+            body: b"fresh content",
+            seen_resume_from: Cell::new(None),
+        };
+
+        let path = cfg.download("http://example.com/f", &hash, &backend).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"fresh content");
+    }
+
+    #[test]
+    fn checksum_failure_reports_expected_and_calculated() {
+        let dir = temp_dir("download-bad-checksum");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let backend = MockBackend {
+            response: DownloadResponse::FullContent,
+            body: b"actual bytes",
+            seen_resume_from: Cell::new(None),
+        };
+        let wrong_hash = utils::sha256_file_from_bytes(b"expected bytes");
+
+        let err = cfg
+            .download("http://example.com/f", &wrong_hash, &backend)
+            .unwrap_err();
+
+        match err {
+            Error::ChecksumFailed {
+                expected,
+                calculated,
+                ..
+            } => {
+                assert_eq!(expected, wrong_hash);
+                assert_eq!(calculated, utils::sha256_file_from_bytes(b"actual bytes"));
+                assert_ne!(expected, calculated);
+            }
+            other => panic!("expected ChecksumFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn progress_callback_sees_cumulative_bytes() {
+        let dir = temp_dir("download-progress");
+        let hash = utils::sha256_file_from_bytes(b"hello world");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let backend = MockBackend {
+            response: DownloadResponse::FullContent,
+            body: b"hello world",
+            seen_resume_from: Cell::new(None),
+        };
+        let seen = std::cell::RefCell::new(Vec::new());
+
+        cfg.fetch(
+            "http://example.com/f",
+            &hash,
+            &backend,
+            Some(&|bytes| seen.borrow_mut().push(bytes)),
+        )
+        .unwrap();
+
+        assert_eq!(seen.into_inner(), vec![11]);
+    }
+}
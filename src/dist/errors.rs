@@ -0,0 +1,65 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    ChecksumFailed {
+        url: String,
+        expected: String,
+        calculated: String,
+    },
+    SignatureFailed {
+        url: String,
+    },
+    UntrustedSignature {
+        url: String,
+        key_id: String,
+    },
+    Download(String),
+    BackendUnavailable(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Error::ChecksumFailed {
+                ref url,
+                ref expected,
+                ref calculated,
+            } => write!(
+                f,
+                "checksum did not match for '{}': expected {}, calculated {}",
+                url, expected, calculated
+            ),
+            Error::SignatureFailed { ref url } => {
+                write!(f, "signature verification failed for '{}'", url)
+            }
+            Error::UntrustedSignature { ref url, ref key_id } => write!(
+                f,
+                "signature for '{}' was made with untrusted key '{}'",
+                url, key_id
+            ),
+            Error::Download(ref msg) => write!(f, "download failed: {}", msg),
+            Error::BackendUnavailable(ref msg) => write!(f, "download backend unavailable: {}", msg),
+            Error::Io(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
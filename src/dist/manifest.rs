@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::dist::download::{DownloadBackend, DownloadCfg};
+use crate::dist::errors::*;
+use crate::dist::notifications::Notification;
+use crate::settings::RetrySettings;
+
+/// Small deterministic-ish jitter added on top of the exponential backoff so
+/// that many clients retrying the same flaky manifest don't all hammer the
+/// CDN in lockstep. Abstracted behind a trait so tests can supply a fixed
+/// value instead of depending on real randomness.
+pub trait JitterSource {
+    fn jitter_ms(&self, max: u64) -> u64;
+}
+
+pub struct NoJitter;
+
+impl JitterSource for NoJitter {
+    fn jitter_ms(&self, _max: u64) -> u64 {
+        0
+    }
+}
+
+/// Fetches `sha256_url` (the manifest's paired `.sha256` resource) and
+/// parses out the hex digest, which is conventionally the first
+/// whitespace-separated field (as produced by `sha256sum`). Goes straight
+/// through `backend` into an in-memory buffer rather than through
+/// `DownloadCfg::fetch`, since that cache is keyed by the very hash this
+/// is trying to discover.
+fn fetch_expected_hash(sha256_url: &str, backend: &dyn DownloadBackend) -> Result<String> {
+    let mut buf = Vec::new();
+    backend.download_to(sha256_url, 0, &mut buf)?;
+    String::from_utf8_lossy(&buf)
+        .split_whitespace()
+        .next()
+        .map(str::to_owned)
+        .ok_or_else(|| Error::Download(format!("empty checksum file at '{}'", sha256_url)))
+}
+
+/// Downloads a manifest `.toml` and its `.sha256`, retrying with bounded
+/// exponential backoff when the checksum doesn't match — a freshly
+/// published manifest can momentarily disagree with its checksum on CDN
+/// edges, and the disagreement can be on either side. Each attempt
+/// refetches both the `.toml` and the `.sha256` from scratch, since a
+/// retry that keeps reusing the first attempt's hash can never converge
+/// if the stale half of the pair was the hash rather than the body.
+pub fn dl_v2_manifest(
+    cfg: &DownloadCfg<'_>,
+    url: &str,
+    sha256_url: &str,
+    retry: RetrySettings,
+    backend: &dyn DownloadBackend,
+    jitter: &dyn JitterSource,
+    sleep: &dyn Fn(Duration),
+) -> Result<PathBuf> {
+    let mut attempt = 0;
+    loop {
+        let hash = fetch_expected_hash(sha256_url, backend)?;
+        match cfg.download(url, &hash, backend) {
+            Ok(path) => return Ok(path),
+            Err(Error::ChecksumFailed { .. }) if attempt < retry.max_retries => {
+                attempt += 1;
+                (cfg.notify_handler)(Notification::RetryingManifestDownload(
+                    url,
+                    attempt,
+                    retry.max_retries,
+                ));
+                let delay = retry.backoff_delay_ms(attempt) + jitter.jitter_ms(250);
+                sleep(Duration::from_millis(delay));
+            }
+            Err(Error::ChecksumFailed { calculated, .. }) => {
+                (cfg.notify_handler)(Notification::ManifestChecksumFailedHack);
+                return Err(Error::ChecksumFailed {
+                    url: url.to_owned(),
+                    expected: hash,
+                    calculated,
+                });
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::download::DownloadResponse;
+    use crate::dist::test_support::temp_dir;
+    use std::cell::Cell;
+    use std::io::Write;
+
+    const MANIFEST_URL: &str = "http://example.com/manifest.toml";
+    const SHA256_URL: &str = "http://example.com/manifest.toml.sha256";
+
+    /// Simulates a `.toml` body and its paired `.sha256` resource becoming
+    /// consistent independently of each other - either side can be the one
+    /// lagging behind on a given CDN edge.
+    struct FlakyBackend {
+        body_calls: Cell<u32>,
+        hash_calls: Cell<u32>,
+        body_succeeds_on_call: u32,
+        hash_succeeds_on_call: u32,
+        good_body: &'static [u8],
+        good_hash: String,
+    }
+
+    impl DownloadBackend for FlakyBackend {
+        fn download_to(
+            &self,
+            url: &str,
+            _resume_from: u64,
+            dest: &mut dyn Write,
+        ) -> Result<DownloadResponse> {
+            if url == SHA256_URL {
+                let call = self.hash_calls.get() + 1;
+                self.hash_calls.set(call);
+                if call >= self.hash_succeeds_on_call {
+                    dest.write_all(self.good_hash.as_bytes())?;
+                } else {
+                    dest.write_all(b"0000000000000000000000000000000000000000000000000000000000000000")?;
+                }
+            } else {
+                let call = self.body_calls.get() + 1;
+                self.body_calls.set(call);
+                if call >= self.body_succeeds_on_call {
+                    dest.write_all(self.good_body)?;
+                } else {
+                    dest.write_all(b"wrong bytes")?;
+                }
+            }
+            Ok(DownloadResponse::FullContent)
+        }
+    }
+
+    fn generous_retries() -> RetrySettings {
+        RetrySettings {
+            max_retries: 5,
+            base_backoff_ms: 1,
+            max_backoff_ms: 1,
+        }
+    }
+
+    #[test]
+    fn retries_until_checksum_matches_then_succeeds() {
+        let dir = temp_dir("manifest-succeeds");
+        let good_hash = crate::utils::sha256_file_from_bytes(b"manifest contents");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let backend = FlakyBackend {
+            body_calls: Cell::new(0),
+            hash_calls: Cell::new(0),
+            body_succeeds_on_call: 3,
+            hash_succeeds_on_call: 1,
+            good_body: b"manifest contents",
+            good_hash,
+        };
+
+        let result = dl_v2_manifest(
+            &cfg,
+            MANIFEST_URL,
+            SHA256_URL,
+            generous_retries(),
+            &backend,
+            &NoJitter,
+            &|_| {},
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(backend.body_calls.get(), 3);
+    }
+
+    #[test]
+    fn converges_when_the_sha256_side_is_the_stale_one() {
+        let dir = temp_dir("manifest-stale-hash");
+        let good_hash = crate::utils::sha256_file_from_bytes(b"manifest contents");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        // The body is right from the first attempt; the `.sha256` resource
+        // is the one lagging behind. A loop that reused the first attempt's
+        // hash forever would never succeed here.
+        let backend = FlakyBackend {
+            body_calls: Cell::new(0),
+            hash_calls: Cell::new(0),
+            body_succeeds_on_call: 1,
+            hash_succeeds_on_call: 3,
+            good_body: b"manifest contents",
+            good_hash,
+        };
+
+        let result = dl_v2_manifest(
+            &cfg,
+            MANIFEST_URL,
+            SHA256_URL,
+            generous_retries(),
+            &backend,
+            &NoJitter,
+            &|_| {},
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(backend.hash_calls.get(), 3);
+        // Every retry must have refetched the `.sha256`, not just the body.
+        assert_eq!(backend.hash_calls.get(), backend.body_calls.get());
+    }
+
+    #[test]
+    fn gives_up_and_surfaces_hack_error_after_max_retries() {
+        let dir = temp_dir("manifest-gives-up");
+        let good_hash = crate::utils::sha256_file_from_bytes(b"manifest contents");
+        let hack_reported = std::sync::atomic::AtomicBool::new(false);
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|n| {
+                if matches!(n, Notification::ManifestChecksumFailedHack) {
+                    hack_reported.store(true, std::sync::atomic::Ordering::SeqCst);
+                }
+            },
+        };
+        let backend = FlakyBackend {
+            body_calls: Cell::new(0),
+            hash_calls: Cell::new(0),
+            body_succeeds_on_call: 100,
+            hash_succeeds_on_call: 1,
+            good_body: b"manifest contents",
+            good_hash,
+        };
+
+        let result = dl_v2_manifest(
+            &cfg,
+            MANIFEST_URL,
+            SHA256_URL,
+            RetrySettings {
+                max_retries: 2,
+                base_backoff_ms: 1,
+                max_backoff_ms: 1,
+            },
+            &backend,
+            &NoJitter,
+            &|_| {},
+        );
+
+        assert!(result.is_err());
+        assert_eq!(backend.body_calls.get(), 3); // initial attempt + 2 retries
+        assert!(hack_reported.load(std::sync::atomic::Ordering::SeqCst));
+    }
+}
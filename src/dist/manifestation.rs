@@ -0,0 +1,227 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::dist::download::{DownloadBackend, DownloadCfg};
+use crate::dist::errors::*;
+use crate::dist::notifications::Notification;
+
+/// Default number of components fetched in parallel when no override is
+/// configured.
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+pub struct ComponentDownload {
+    pub name: String,
+    pub url: String,
+    pub hash: String,
+}
+
+/// Fetches up to `max_concurrent` components in parallel, each landing in
+/// the persistent cache by hash exactly as a serial download would, so a
+/// retry after a partial failure can reuse whatever already completed.
+/// Results are returned in the same order as `components`, regardless of
+/// which download finished first, so callers can install them in order.
+/// `progress` is called with a component's name and its cumulative bytes
+/// written as each one streams in, so a CLI can render one progress bar
+/// per component rather than a single aggregate count.
+pub fn download_components_concurrently(
+    cfg: &DownloadCfg<'_>,
+    components: &[ComponentDownload],
+    backend: &(dyn DownloadBackend + Sync),
+    max_concurrent: usize,
+    progress: &(dyn Fn(&str, u64) + Sync),
+) -> Vec<Result<PathBuf>> {
+    if components.is_empty() {
+        return Vec::new();
+    }
+
+    (cfg.notify_handler)(Notification::DownloadingComponents(components.len()));
+
+    let max_concurrent = max_concurrent.max(1).min(components.len());
+    let results: Mutex<Vec<Option<Result<PathBuf>>>> =
+        Mutex::new((0..components.len()).map(|_| None).collect());
+    let next_index = Mutex::new(0usize);
+
+    std::thread::scope(|scope| {
+        for _ in 0..max_concurrent {
+            scope.spawn(|| loop {
+                let idx = {
+                    let mut guard = next_index.lock().unwrap();
+                    if *guard >= components.len() {
+                        break;
+                    }
+                    let i = *guard;
+                    *guard += 1;
+                    i
+                };
+                let component = &components[idx];
+                let result = cfg
+                    .fetch(
+                        &component.url,
+                        &component.hash,
+                        backend,
+                        Some(&|bytes| progress(&component.name, bytes)),
+                    )
+                    .and_then(|path| {
+                        cfg.verify_checksum(&path, &component.url, &component.hash)?;
+                        Ok(path)
+                    });
+                results.lock().unwrap()[idx] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every index is claimed exactly once"))
+        .collect()
+}
+
+/// Installs downloaded components strictly serially and in order, so the
+/// existing transactional `RollingBack` semantics (stop and unwind on the
+/// first failure) are unaffected by downloads having happened concurrently.
+pub fn install_downloaded_components<F>(
+    cfg: &DownloadCfg<'_>,
+    downloads: Vec<Result<PathBuf>>,
+    mut install: F,
+) -> Result<()>
+where
+    F: FnMut(PathBuf) -> Result<()>,
+{
+    for download in downloads {
+        let path = download.inspect_err(|_| {
+            (cfg.notify_handler)(Notification::RollingBack);
+        })?;
+        if let Err(e) = install(path) {
+            (cfg.notify_handler)(Notification::RollingBack);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::download::DownloadResponse;
+    use crate::dist::test_support::temp_dir;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct EchoBackend;
+
+    impl DownloadBackend for EchoBackend {
+        fn download_to(
+            &self,
+            url: &str,
+            _resume_from: u64,
+            dest: &mut dyn Write,
+        ) -> Result<DownloadResponse> {
+            dest.write_all(url.as_bytes())?;
+            Ok(DownloadResponse::FullContent)
+        }
+    }
+
+    #[test]
+    fn downloads_all_components_and_preserves_order() {
+        let dir = temp_dir("manifestation-order");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let urls = [
+            "http://example.com/rustc",
+            "http://example.com/cargo",
+            "http://example.com/rust-std",
+            "http://example.com/clippy",
+        ];
+        let components: Vec<_> = urls
+            .iter()
+            .map(|url| ComponentDownload {
+                name: url.to_string(),
+                url: url.to_string(),
+                hash: crate::utils::sha256_file_from_bytes(url.as_bytes()),
+            })
+            .collect();
+
+        let results =
+            download_components_concurrently(&cfg, &components, &EchoBackend, 2, &|_, _| {});
+
+        assert_eq!(results.len(), urls.len());
+        for (result, url) in results.iter().zip(urls.iter()) {
+            let path = result.as_ref().unwrap();
+            assert_eq!(fs::read(path).unwrap(), url.as_bytes());
+        }
+    }
+
+    #[test]
+    fn reports_progress_keyed_by_component_name() {
+        let dir = temp_dir("manifestation-progress");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let urls = ["http://example.com/rustc", "http://example.com/cargo"];
+        let components: Vec<_> = urls
+            .iter()
+            .map(|url| ComponentDownload {
+                name: url.split('/').next_back().unwrap().to_owned(),
+                url: url.to_string(),
+                hash: crate::utils::sha256_file_from_bytes(url.as_bytes()),
+            })
+            .collect();
+        let seen: Mutex<HashMap<String, u64>> = Mutex::new(HashMap::new());
+
+        let results = download_components_concurrently(
+            &cfg,
+            &components,
+            &EchoBackend,
+            2,
+            &|name, bytes| {
+                seen.lock().unwrap().insert(name.to_owned(), bytes);
+            },
+        );
+
+        assert!(results.iter().all(|r| r.is_ok()));
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(seen.get("rustc"), Some(&("http://example.com/rustc".len() as u64)));
+        assert_eq!(seen.get("cargo"), Some(&("http://example.com/cargo".len() as u64)));
+    }
+
+    #[test]
+    fn install_stops_and_rolls_back_on_first_failure() {
+        let dir = temp_dir("manifestation-rollback");
+        let installed = AtomicUsize::new(0);
+        let rolled_back = AtomicUsize::new(0);
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|n| {
+                if matches!(n, Notification::RollingBack) {
+                    rolled_back.fetch_add(1, Ordering::SeqCst);
+                }
+            },
+        };
+
+        let downloads = vec![
+            Ok(dir.join("a")),
+            Err(Error::ChecksumFailed {
+                url: "b".to_owned(),
+                expected: String::new(),
+                calculated: String::new(),
+            }),
+            Ok(dir.join("c")),
+        ];
+
+        let result = install_downloaded_components(&cfg, downloads, |_path| {
+            installed.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        assert_eq!(installed.load(Ordering::SeqCst), 1); // only "a", stopped before "c"
+        assert_eq!(rolled_back.load(Ordering::SeqCst), 1);
+    }
+}
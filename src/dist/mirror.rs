@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use crate::dist::download::{DownloadBackend, DownloadCfg};
+use crate::dist::errors::*;
+use crate::dist::notifications::Notification;
+
+/// Tries an ordered list of mirror base URLs in sequence for a single
+/// `path` (e.g. `dist/channel-rust-stable.toml`), falling over to the
+/// next mirror on a connection error or unavailable backend. The content
+/// hash comes from the canonical manifest and is the same for every
+/// mirror, so `DownloadCfg::download`'s checksum check still guarantees a
+/// malicious or stale mirror can't substitute different content — a
+/// checksum or signature failure is not a mirror problem and is returned
+/// immediately rather than tried against the next mirror.
+pub fn download_with_mirrors(
+    cfg: &DownloadCfg<'_>,
+    mirrors: &[String],
+    path: &str,
+    hash: &str,
+    backend: &dyn DownloadBackend,
+) -> Result<PathBuf> {
+    if mirrors.is_empty() {
+        return Err(Error::Download("no mirrors configured".to_owned()));
+    }
+
+    let mut last_err = None;
+    for (i, mirror) in mirrors.iter().enumerate() {
+        let url = format!("{}/{}", mirror.trim_end_matches('/'), path);
+        (cfg.notify_handler)(Notification::TryingMirror(&url));
+
+        match cfg.download(&url, hash, backend) {
+            Ok(downloaded) => return Ok(downloaded),
+            Err(Error::Download(msg)) | Err(Error::BackendUnavailable(msg)) => {
+                if let Some(next_mirror) = mirrors.get(i + 1) {
+                    let next_url = format!("{}/{}", next_mirror.trim_end_matches('/'), path);
+                    (cfg.notify_handler)(Notification::MirrorFailedOver(&url, &next_url));
+                }
+                last_err = Some(Error::Download(msg));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| Error::Download("all mirrors exhausted".to_owned())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::download::DownloadResponse;
+    use crate::dist::test_support::temp_dir;
+    use std::io::Write;
+    use std::sync::Mutex;
+
+    struct PerUrlBackend {
+        failing_urls: Vec<&'static str>,
+        body: &'static [u8],
+    }
+
+    impl DownloadBackend for PerUrlBackend {
+        fn download_to(
+            &self,
+            url: &str,
+            _resume_from: u64,
+            dest: &mut dyn Write,
+        ) -> Result<DownloadResponse> {
+            if self.failing_urls.iter().any(|f| url.contains(f)) {
+                return Err(Error::BackendUnavailable(url.to_owned()));
+            }
+            dest.write_all(self.body)?;
+            Ok(DownloadResponse::FullContent)
+        }
+    }
+
+    #[test]
+    fn falls_over_to_next_mirror_on_backend_unavailable() {
+        let dir = temp_dir("mirror-failover");
+        let events = Mutex::new(Vec::new());
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|n| events.lock().unwrap().push(n.to_string()),
+        };
+        let hash = crate::utils::sha256_file_from_bytes(b"manifest contents");
+        let backend = PerUrlBackend {
+            failing_urls: vec!["mirror-a"],
+            body: b"manifest contents",
+        };
+        let mirrors = vec![
+            "https://mirror-a".to_owned(),
+            "https://mirror-b".to_owned(),
+        ];
+
+        let result =
+            download_with_mirrors(&cfg, &mirrors, "dist/channel-rust-stable.toml", &hash, &backend);
+
+        assert!(result.is_ok());
+        let events = events.into_inner().unwrap();
+        assert!(events.iter().any(|e| e.contains("mirror-a") && e.contains("falling back")));
+    }
+
+    #[test]
+    fn checksum_failure_is_not_retried_against_other_mirrors() {
+        let dir = temp_dir("mirror-checksum-not-mirror-recoverable");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let backend = PerUrlBackend {
+            failing_urls: vec![],
+            body: b"wrong bytes",
+        };
+        let mirrors = vec![
+            "https://mirror-a".to_owned(),
+            "https://mirror-b".to_owned(),
+        ];
+        let canonical_hash = crate::utils::sha256_file_from_bytes(b"expected bytes");
+
+        let result = download_with_mirrors(
+            &cfg,
+            &mirrors,
+            "dist/channel-rust-stable.toml",
+            &canonical_hash,
+            &backend,
+        );
+
+        assert!(matches!(result, Err(Error::ChecksumFailed { .. })));
+    }
+}
@@ -0,0 +1,12 @@
+#[allow(clippy::module_inception)]
+pub mod dist;
+pub mod download;
+pub mod errors;
+pub mod manifest;
+pub mod manifestation;
+pub mod mirror;
+pub mod notifications;
+pub mod signature;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod version_req;
@@ -24,6 +24,15 @@ pub enum Notification<'a> {
     DownloadingLegacyManifest,
     ManifestChecksumFailedHack,
     ComponentUnavailable(&'a str, Option<&'a TargetTriple>),
+    ResumingPartialDownload(&'a str, u64),
+    RetryingManifestDownload(&'a str, u32, u32),
+    VerifyingSignature(&'a str),
+    SignatureInvalid(&'a str),
+    UntrustedSignatureKey(&'a str),
+    DownloadingComponents(usize),
+    ResolvedVersionRequirement(&'a str, &'a str),
+    TryingMirror(&'a str),
+    MirrorFailedOver(&'a str, &'a str),
 }
 
 impl<'a> From<crate::utils::Notification<'a>> for Notification<'a> {
@@ -40,6 +49,7 @@ impl<'a> Notification<'a> {
             ChecksumValid(_) | FileAlreadyDownloaded | DownloadingLegacyManifest => {
                 NotificationLevel::Verbose
             }
+            VerifyingSignature(_) | TryingMirror(_) => NotificationLevel::Verbose,
             SignatureValid(_)
             | DownloadingComponent(_, _, _)
             | InstallingComponent(_, _, _)
@@ -48,12 +58,19 @@ impl<'a> Notification<'a> {
             | ManifestChecksumFailedHack
             | RollingBack
             | DownloadingManifest(_)
-            | DownloadedManifest(_, _) => NotificationLevel::Info,
+            | DownloadedManifest(_, _)
+            | ResumingPartialDownload(_, _)
+            | RetryingManifestDownload(_, _, _)
+            | DownloadingComponents(_)
+            | ResolvedVersionRequirement(_, _) => NotificationLevel::Info,
             ExtensionNotInstalled(_)
             | MissingInstalledComponent(_)
             | CachedFileChecksumFailed
-            | ComponentUnavailable(_, _) => NotificationLevel::Warn,
-            NonFatalError(_) => NotificationLevel::Error,
+            | ComponentUnavailable(_, _)
+            | MirrorFailedOver(_, _) => NotificationLevel::Warn,
+            NonFatalError(_) | SignatureInvalid(_) | UntrustedSignatureKey(_) => {
+                NotificationLevel::Error
+            }
         }
     }
 }
@@ -73,39 +90,34 @@ impl<'a> Display for Notification<'a> {
             MissingInstalledComponent(c) => {
                 write!(f, "during uninstall component {} was not found", c)
             }
-            DownloadingComponent(c, h, t) => {
-                if Some(h) == t || t.is_none() {
-                    write!(f, "downloading component '{}'", c)
-                } else {
-                    write!(f, "downloading component '{}' for '{}'", c, t.unwrap())
+            DownloadingComponent(c, h, t) => match t {
+                Some(t) if Some(h) != Some(t) => {
+                    write!(f, "downloading component '{}' for '{}'", c, t)
                 }
-            }
-            InstallingComponent(c, h, t) => {
-                if Some(h) == t || t.is_none() {
-                    write!(f, "installing component '{}'", c)
-                } else {
-                    write!(f, "installing component '{}' for '{}'", c, t.unwrap())
+                _ => write!(f, "downloading component '{}'", c),
+            },
+            InstallingComponent(c, h, t) => match t {
+                Some(t) if Some(h) != Some(t) => {
+                    write!(f, "installing component '{}' for '{}'", c, t)
                 }
-            }
-            RemovingComponent(c, h, t) => {
-                if Some(h) == t || t.is_none() {
-                    write!(f, "removing component '{}'", c)
-                } else {
-                    write!(f, "removing component '{}' for '{}'", c, t.unwrap())
+                _ => write!(f, "installing component '{}'", c),
+            },
+            RemovingComponent(c, h, t) => match t {
+                Some(t) if Some(h) != Some(t) => {
+                    write!(f, "removing component '{}' for '{}'", c, t)
                 }
-            }
-            RemovingOldComponent(c, h, t) => {
-                if Some(h) == t || t.is_none() {
-                    write!(f, "removing previous version of component '{}'", c)
-                } else {
+                _ => write!(f, "removing component '{}'", c),
+            },
+            RemovingOldComponent(c, h, t) => match t {
+                Some(t) if Some(h) != Some(t) => {
                     write!(
                         f,
                         "removing previous version of component '{}' for '{}'",
-                        c,
-                        t.unwrap()
+                        c, t
                     )
                 }
-            }
+                _ => write!(f, "removing previous version of component '{}'", c),
+            },
             DownloadingManifest(t) => write!(f, "syncing channel updates for '{}'", t),
             DownloadedManifest(date, Some(version)) => {
                 write!(f, "latest update on {}, rust version {}", date, version)
@@ -128,6 +140,29 @@ impl<'a> Display for Notification<'a> {
                     write!(f, "component '{}' is not available anymore", pkg)
                 }
             }
+            ResumingPartialDownload(c, bytes) => {
+                write!(f, "resuming download of '{}' from {} bytes", c, bytes)
+            }
+            RetryingManifestDownload(url, attempt, max_attempts) => {
+                write!(
+                    f,
+                    "checksum mismatch downloading '{}', retry {} of {}",
+                    url, attempt, max_attempts
+                )
+            }
+            VerifyingSignature(c) => write!(f, "verifying signature for '{}'", c),
+            SignatureInvalid(c) => write!(f, "signature for '{}' is not valid", c),
+            UntrustedSignatureKey(c) => {
+                write!(f, "signature for '{}' was made with an untrusted key", c)
+            }
+            DownloadingComponents(n) => write!(f, "downloading {} components", n),
+            ResolvedVersionRequirement(req, version) => {
+                write!(f, "'{}' resolved to rust {}", req, version)
+            }
+            TryingMirror(url) => write!(f, "trying mirror '{}'", url),
+            MirrorFailedOver(from, to) => {
+                write!(f, "mirror '{}' failed, falling back to '{}'", from, to)
+            }
         }
     }
 }
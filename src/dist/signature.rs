@@ -0,0 +1,284 @@
+use std::path::PathBuf;
+
+use crate::dist::download::{DownloadBackend, DownloadCfg};
+use crate::dist::errors::*;
+use crate::dist::notifications::Notification;
+use crate::settings::SignaturePolicy;
+
+/// Key IDs of the official release signing keys, bundled into the binary
+/// so verification doesn't depend on fetching a keyring at runtime. Real
+/// deployments would embed the actual public key material here. This is
+/// the set to build the official root's `TrustedKeyring` from;
+/// `verify_signed_file` itself trusts exactly the keyring it is given.
+pub const OFFICIAL_TRUSTED_KEYS: &[&str] = &["85AB96E6FA1BE5FE"];
+
+pub struct TrustedKeyring<'a> {
+    trusted_key_ids: &'a [&'a str],
+}
+
+impl<'a> TrustedKeyring<'a> {
+    pub fn new(trusted_key_ids: &'a [&'a str]) -> Self {
+        TrustedKeyring { trusted_key_ids }
+    }
+
+    fn is_trusted(&self, key_id: &str) -> bool {
+        self.trusted_key_ids.contains(&key_id)
+    }
+}
+
+/// The key that produced a signature which cryptographically checks out,
+/// prior to any trust decision about that key.
+pub struct VerifiedSignature {
+    pub key_id: String,
+}
+
+/// Abstracts over the actual OpenPGP implementation so the trust/policy
+/// logic below can be tested without a real keypair.
+pub trait SignatureBackend {
+    fn verify(&self, data: &[u8], signature: &[u8]) -> Result<VerifiedSignature>;
+}
+
+/// A content file and its paired detached-signature file, both identified
+/// by URL plus the hash expected from the canonical manifest.
+pub struct SignedDownload<'a> {
+    pub url: &'a str,
+    pub content_hash: &'a str,
+    pub sig_url: &'a str,
+    pub sig_hash: &'a str,
+}
+
+/// Downloads `download.url`'s content and its detached `.asc` signature,
+/// then verifies the signature before returning the content path.
+/// Verification happens on the downloaded-but-not-yet-installed file, so a
+/// bad signature aborts before anything is unpacked.
+pub fn verify_signed_file(
+    cfg: &DownloadCfg<'_>,
+    download: SignedDownload<'_>,
+    dl_backend: &dyn DownloadBackend,
+    sig_backend: &dyn SignatureBackend,
+    keyring: &TrustedKeyring<'_>,
+    policy: SignaturePolicy,
+) -> Result<PathBuf> {
+    let url = download.url;
+    // Fetch the content without trusting `ChecksumValid` yet: under
+    // `SignaturePolicy::Verify` a forged file must be rejected by the
+    // signature check before the checksum is ever treated as meaningful,
+    // so the checksum is verified only after (or alongside, for Off) the
+    // signature outcome is known.
+    let content_path = cfg.fetch(url, download.content_hash, dl_backend, None)?;
+
+    if policy == SignaturePolicy::Off {
+        cfg.verify_checksum(&content_path, url, download.content_hash)?;
+        return Ok(content_path);
+    }
+
+    (cfg.notify_handler)(Notification::VerifyingSignature(url));
+    let sig_path = cfg.download(download.sig_url, download.sig_hash, dl_backend)?;
+
+    let data = std::fs::read(&content_path)?;
+    let signature = std::fs::read(&sig_path)?;
+
+    let outcome = sig_backend.verify(&data, &signature).and_then(|verified| {
+        if keyring.is_trusted(&verified.key_id) {
+            Ok(verified)
+        } else {
+            Err(Error::UntrustedSignature {
+                url: url.to_owned(),
+                key_id: verified.key_id,
+            })
+        }
+    });
+
+    match outcome {
+        Ok(_) => {
+            cfg.verify_checksum(&content_path, url, download.content_hash)?;
+            Ok(content_path)
+        }
+        Err(e @ Error::UntrustedSignature { .. }) => {
+            (cfg.notify_handler)(Notification::UntrustedSignatureKey(url));
+            if policy == SignaturePolicy::Warn {
+                cfg.verify_checksum(&content_path, url, download.content_hash)?;
+                Ok(content_path)
+            } else {
+                Err(e)
+            }
+        }
+        Err(e) => {
+            (cfg.notify_handler)(Notification::SignatureInvalid(url));
+            if policy == SignaturePolicy::Warn {
+                cfg.verify_checksum(&content_path, url, download.content_hash)?;
+                Ok(content_path)
+            } else {
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist::download::DownloadResponse;
+    use crate::dist::test_support::temp_dir;
+    use std::io::Write;
+
+    struct StaticDownload;
+
+    impl DownloadBackend for StaticDownload {
+        fn download_to(
+            &self,
+            url: &str,
+            _resume_from: u64,
+            dest: &mut dyn Write,
+        ) -> Result<DownloadResponse> {
+            dest.write_all(url.as_bytes())?;
+            Ok(DownloadResponse::FullContent)
+        }
+    }
+
+    /// `Some(key_id)` simulates a cryptographically valid signature made by
+    /// `key_id`; `None` simulates a signature that fails to verify at all.
+    struct FixedSignature(Option<&'static str>);
+
+    impl SignatureBackend for FixedSignature {
+        fn verify(&self, _data: &[u8], _signature: &[u8]) -> Result<VerifiedSignature> {
+            match self.0 {
+                Some(key_id) => Ok(VerifiedSignature {
+                    key_id: key_id.to_owned(),
+                }),
+                None => Err(Error::SignatureFailed {
+                    url: "sig".to_owned(),
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn trusted_key_passes_under_verify_policy() {
+        let dir = temp_dir("signature-trusted");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let content_hash = crate::utils::sha256_file_from_bytes(b"http://x/content");
+        let sig_hash = crate::utils::sha256_file_from_bytes(b"http://x/content.asc");
+        let keyring = TrustedKeyring::new(OFFICIAL_TRUSTED_KEYS);
+        let sig_backend = FixedSignature(Some(OFFICIAL_TRUSTED_KEYS[0]));
+
+        let result = verify_signed_file(
+            &cfg,
+            SignedDownload {
+                url: "http://x/content",
+                content_hash: &content_hash,
+                sig_url: "http://x/content.asc",
+                sig_hash: &sig_hash,
+            },
+            &StaticDownload,
+            &sig_backend,
+            &keyring,
+            SignaturePolicy::Verify,
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn untrusted_key_aborts_under_verify_policy_but_not_warn() {
+        let keyring = TrustedKeyring::new(OFFICIAL_TRUSTED_KEYS);
+        let sig_backend = FixedSignature(Some("DEADBEEF"));
+
+        for (policy, should_succeed) in [
+            (SignaturePolicy::Verify, false),
+            (SignaturePolicy::Warn, true),
+        ] {
+            let dir = temp_dir(&format!("signature-untrusted-{:?}", policy));
+            let cfg = DownloadCfg {
+                download_dir: &dir,
+                notify_handler: &|_| {},
+            };
+            let content_hash = crate::utils::sha256_file_from_bytes(b"http://x/content");
+            let sig_hash = crate::utils::sha256_file_from_bytes(b"http://x/content.asc");
+
+            let result = verify_signed_file(
+                &cfg,
+                SignedDownload {
+                    url: "http://x/content",
+                    content_hash: &content_hash,
+                    sig_url: "http://x/content.asc",
+                    sig_hash: &sig_hash,
+                },
+                &StaticDownload,
+                &sig_backend,
+                &keyring,
+                policy,
+            );
+
+            assert_eq!(result.is_ok(), should_succeed);
+        }
+    }
+
+    #[test]
+    fn off_policy_skips_signature_download_entirely() {
+        let dir = temp_dir("signature-off");
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|_| {},
+        };
+        let content_hash = crate::utils::sha256_file_from_bytes(b"http://x/content");
+        let keyring = TrustedKeyring::new(OFFICIAL_TRUSTED_KEYS);
+        let sig_backend = FixedSignature(None);
+
+        let result = verify_signed_file(
+            &cfg,
+            SignedDownload {
+                url: "http://x/content",
+                content_hash: &content_hash,
+                sig_url: "http://x/content.asc",
+                sig_hash: "unused-hash-not-downloaded",
+            },
+            &StaticDownload,
+            &sig_backend,
+            &keyring,
+            SignaturePolicy::Off,
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn bad_signature_aborts_before_checksum_is_trusted() {
+        let dir = temp_dir("signature-bad-sig-checksum-order");
+        let content_checksum_validated = std::sync::atomic::AtomicBool::new(false);
+        let cfg = DownloadCfg {
+            download_dir: &dir,
+            notify_handler: &|n| {
+                if matches!(n, Notification::ChecksumValid(u) if u == "http://x/content") {
+                    content_checksum_validated.store(true, std::sync::atomic::Ordering::SeqCst);
+                }
+            },
+        };
+        let content_hash = crate::utils::sha256_file_from_bytes(b"http://x/content");
+        let sig_hash = crate::utils::sha256_file_from_bytes(b"http://x/content.asc");
+        let keyring = TrustedKeyring::new(OFFICIAL_TRUSTED_KEYS);
+        let sig_backend = FixedSignature(None);
+
+        let result = verify_signed_file(
+            &cfg,
+            SignedDownload {
+                url: "http://x/content",
+                content_hash: &content_hash,
+                sig_url: "http://x/content.asc",
+                sig_hash: &sig_hash,
+            },
+            &StaticDownload,
+            &sig_backend,
+            &keyring,
+            SignaturePolicy::Verify,
+        );
+
+        assert!(result.is_err());
+        // The content's checksum must never be reported valid when the
+        // signature check is what ultimately fails the download.
+        assert!(!content_checksum_validated.load(std::sync::atomic::Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,15 @@
+//! Fixtures shared by the `dist` submodules' test suites.
+#![cfg(test)]
+
+use std::fs;
+use std::path::PathBuf;
+
+/// A fresh, empty temporary directory scoped to `name`, which should be
+/// unique per test (e.g. `"download-resume-206"`) so concurrently running
+/// tests don't stomp on each other's fixtures.
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustup-test-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
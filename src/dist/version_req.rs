@@ -0,0 +1,135 @@
+use semver::{Version, VersionReq};
+
+use crate::dist::errors::*;
+use crate::dist::notifications::Notification;
+
+/// How a toolchain was requested on the command line: the rolling `stable`
+/// channel, an exact released version, or a semver requirement like
+/// `^1.70` / `>=1.68, <1.72` that should resolve to the newest release
+/// satisfying it.
+#[derive(Debug, Clone)]
+pub enum ToolchainRequirement {
+    Latest,
+    Exact(Version),
+    Range(VersionReq),
+}
+
+impl ToolchainRequirement {
+    pub fn parse(s: &str) -> Result<Self> {
+        if s == "stable" || s == "latest" {
+            return Ok(ToolchainRequirement::Latest);
+        }
+        if let Ok(v) = Version::parse(s) {
+            return Ok(ToolchainRequirement::Exact(v));
+        }
+        VersionReq::parse(s)
+            .map(ToolchainRequirement::Range)
+            .map_err(|e| Error::Download(format!("invalid version requirement '{}': {}", s, e)))
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        match self {
+            ToolchainRequirement::Latest => true,
+            ToolchainRequirement::Exact(v) => v == version,
+            ToolchainRequirement::Range(req) => req.matches(version),
+        }
+    }
+}
+
+/// Picks the highest released stable version satisfying `requirement`,
+/// out of the channel/manifest history in `available_releases`.
+fn resolve(requirement: &ToolchainRequirement, available_releases: &[Version]) -> Option<Version> {
+    available_releases
+        .iter()
+        .filter(|v| requirement.matches(v))
+        .max()
+        .cloned()
+}
+
+/// Resolves `requirement_str` (already parsed into `requirement`) against
+/// the known release history, reporting the outcome through the
+/// notification layer: `ResolvedVersionRequirement` on success, or the
+/// existing `ComponentUnavailable` warning path if nothing satisfies it.
+pub fn resolve_and_notify(
+    notify_handler: &dyn Fn(Notification<'_>),
+    requirement_str: &str,
+    requirement: &ToolchainRequirement,
+    available_releases: &[Version],
+) -> Option<Version> {
+    match resolve(requirement, available_releases) {
+        Some(version) => {
+            let version_str = version.to_string();
+            notify_handler(Notification::ResolvedVersionRequirement(
+                requirement_str,
+                &version_str,
+            ));
+            Some(version)
+        }
+        None => {
+            notify_handler(Notification::ComponentUnavailable(requirement_str, None));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_stable_as_latest() {
+        assert!(matches!(
+            ToolchainRequirement::parse("stable").unwrap(),
+            ToolchainRequirement::Latest
+        ));
+    }
+
+    #[test]
+    fn parses_exact_version() {
+        assert!(matches!(
+            ToolchainRequirement::parse("1.70.0").unwrap(),
+            ToolchainRequirement::Exact(_)
+        ));
+    }
+
+    #[test]
+    fn parses_caret_range() {
+        assert!(matches!(
+            ToolchainRequirement::parse("^1.70").unwrap(),
+            ToolchainRequirement::Range(_)
+        ));
+    }
+
+    #[test]
+    fn resolves_to_highest_matching_release() {
+        let releases = vec![v("1.68.0"), v("1.70.0"), v("1.70.1"), v("1.72.0")];
+        let req = ToolchainRequirement::parse(">=1.70, <1.72").unwrap();
+
+        let resolved = resolve(&req, &releases);
+
+        assert_eq!(resolved, Some(v("1.70.1")));
+    }
+
+    #[test]
+    fn reports_unavailable_when_nothing_matches() {
+        let releases = vec![v("1.60.0"), v("1.61.0")];
+        let req = ToolchainRequirement::parse(">=1.68, <1.72").unwrap();
+        let notified = std::cell::RefCell::new(Vec::new());
+
+        let resolved = resolve_and_notify(
+            &|n| notified.borrow_mut().push(n.to_string()),
+            ">=1.68, <1.72",
+            &req,
+            &releases,
+        );
+
+        assert!(resolved.is_none());
+        let notified = notified.into_inner();
+        assert_eq!(notified.len(), 1);
+        assert!(notified[0].contains("not available"));
+    }
+}
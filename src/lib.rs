@@ -0,0 +1,3 @@
+pub mod dist;
+pub mod settings;
+pub mod utils;
@@ -0,0 +1,87 @@
+/// Bounded exponential backoff parameters for retrying a flaky download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetrySettings {
+    /// Number of retries attempted after the initial request, so the
+    /// request is made at most `max_retries + 1` times in total.
+    pub max_retries: u32,
+    pub base_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        RetrySettings {
+            max_retries: 3,
+            base_backoff_ms: 1_000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl RetrySettings {
+    /// Delay before retry attempt number `attempt` (1-indexed), doubling
+    /// each time and capped at `max_backoff_ms`.
+    pub fn backoff_delay_ms(&self, attempt: u32) -> u64 {
+        let shift = attempt.saturating_sub(1).min(20);
+        let exp = self.base_backoff_ms.saturating_mul(1u64 << shift);
+        exp.min(self.max_backoff_ms)
+    }
+}
+
+/// How strictly detached signatures on downloaded manifests/components are
+/// enforced. Lets air-gapped mirrors that don't re-sign their mirrored
+/// files downgrade gracefully instead of being unable to install at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignaturePolicy {
+    /// A missing, invalid, or untrusted signature aborts the install.
+    #[default]
+    Verify,
+    /// An invalid or untrusted signature is reported but the install
+    /// proceeds anyway; a signature file that cannot be downloaded still
+    /// aborts the install.
+    Warn,
+    /// Signatures are not downloaded or checked at all.
+    Off,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub manifest_retry: RetrySettings,
+    pub signature_policy: SignaturePolicy,
+    /// Ordered list of mirror base URLs that `download_with_mirrors` tries
+    /// in sequence; once the last one fails, the download fails, with no
+    /// further fallback. `mirrors_from_env` builds it from the
+    /// comma-separated `RUSTUP_DIST_MIRRORS` value; nothing in this crate
+    /// reads it from a settings file.
+    pub mirrors: Vec<String>,
+}
+
+/// Parses the `RUSTUP_DIST_MIRRORS` environment variable into an ordered
+/// list of mirror base URLs, e.g. `https://mirror-a,https://mirror-b`.
+pub fn mirrors_from_env(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let s = RetrySettings {
+            max_retries: 5,
+            base_backoff_ms: 1_000,
+            max_backoff_ms: 5_000,
+        };
+        assert_eq!(s.backoff_delay_ms(1), 1_000);
+        assert_eq!(s.backoff_delay_ms(2), 2_000);
+        assert_eq!(s.backoff_delay_ms(3), 4_000);
+        assert_eq!(s.backoff_delay_ms(4), 5_000); // capped
+        assert_eq!(s.backoff_delay_ms(5), 5_000); // capped
+    }
+}
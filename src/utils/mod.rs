@@ -0,0 +1,59 @@
+pub mod notify;
+
+use self::notify::NotificationLevel;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum Notification<'a> {
+    CreatingDirectory(&'a str, &'a Path),
+}
+
+impl<'a> Notification<'a> {
+    pub fn level(&self) -> NotificationLevel {
+        use self::Notification::*;
+        match *self {
+            CreatingDirectory(_, _) => NotificationLevel::Verbose,
+        }
+    }
+}
+
+impl<'a> Display for Notification<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::Notification::*;
+        match *self {
+            CreatingDirectory(name, path) => {
+                write!(f, "creating {} directory: '{}'", name, path.display())
+            }
+        }
+    }
+}
+
+/// Hex-encoded SHA-256 digest of the file at `path`.
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hex-encoded SHA-256 digest of an in-memory buffer, mainly useful for
+/// constructing expected hashes in tests.
+pub fn sha256_file_from_bytes(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
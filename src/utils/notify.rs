@@ -0,0 +1,7 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NotificationLevel {
+    Verbose,
+    Info,
+    Warn,
+    Error,
+}